@@ -6,6 +6,7 @@
 
 use core::panic::PanicInfo;
 
+pub mod log;
 pub mod serial;
 pub mod vga_buffer;
 
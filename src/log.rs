@@ -0,0 +1,94 @@
+use crate::serial::SERIAL1;
+use crate::vga_buffer::{Color, ColorCode, WRITER};
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+// 之前内核代码必须手动挑一个输出口：println! 只上屏幕，serial_println! 只
+// 到宿主机 stdout。log 模块把两者接到同一套 log!/info!/warn!/error! 宏后面，
+// 一条记录会同时出现在 QEMU 的屏幕和宿主机终端上。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum LogLevel {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+}
+
+impl LogLevel {
+    fn tag(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+        }
+    }
+
+    // 屏幕上按级别区分颜色，方便一眼看出哪些是错误。
+    fn color_code(self) -> ColorCode {
+        match self {
+            LogLevel::Error => ColorCode::new(Color::LightRed, Color::Black),
+            LogLevel::Warn => ColorCode::new(Color::Yellow, Color::Black),
+            LogLevel::Info => ColorCode::new(Color::White, Color::Black),
+        }
+    }
+}
+
+// 当前允许通过的最高级别，数值越小优先级越高（Error < Warn < Info）；
+// 记录的级别数值小于等于这个阈值才会被打印。用原子量存放是因为日志调用
+// 可能发生在中断上下文里，不能用普通静态可变量。
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+pub fn set_max_level(level: LogLevel) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+#[doc(hidden)]
+pub fn enabled(level: LogLevel) -> bool {
+    (level as u8) <= MAX_LEVEL.load(Ordering::Relaxed)
+}
+
+// 把一条记录同时写到 VGA 缓冲区（带级别对应的颜色）和串口。调用方要保证
+// 在调用之前已经用 enabled() 过滤掉了不该打印的级别，这里只管格式化和输出。
+#[doc(hidden)]
+pub fn _log(level: LogLevel, args: fmt::Arguments) {
+    use core::fmt::Write;
+
+    let mut writer = WRITER.lock();
+    let previous = writer.set_color(level.color_code());
+    let _ = write!(writer, "[{}] ", level.tag());
+    let _ = writer.write_fmt(args);
+    let _ = writer.write_str("\n");
+    writer.set_color(previous);
+    drop(writer);
+
+    let mut serial = SERIAL1.lock();
+    let _ = write!(serial, "[{}] ", level.tag());
+    let _ = serial.write_fmt(args);
+    let _ = serial.write_str("\n");
+}
+
+// log! 在格式化之前先检查级别是否启用，这样被过滤掉的记录既不用排版参数，
+// 也不用去抢 WRITER/SERIAL1 的锁。
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::log::_log($level, format_args!($($arg)*));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => ($crate::log!($crate::log::LogLevel::Info, $($arg)*));
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => ($crate::log!($crate::log::LogLevel::Warn, $($arg)*));
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => ($crate::log!($crate::log::LogLevel::Error, $($arg)*));
+}
@@ -36,3 +36,39 @@ fn panic(info: &PanicInfo) -> ! {
 fn trivial_assertion() {
     assert_eq!(1, 1);
 }
+
+// 校验 cp437_encode 的几个关键分支：ASCII 直接透传、两字节 UTF-8 字符正确
+// 折叠成一个单元格字节、查不到的字符退回 0xfe。
+#[test_case]
+fn cp437_encode_ascii_passthrough() {
+    assert_eq!(minios::vga_buffer::cp437_encode('A'), b'A');
+}
+
+#[test_case]
+fn cp437_encode_high_glyph() {
+    assert_eq!(minios::vga_buffer::cp437_encode('é'), 0x82);
+    assert_eq!(minios::vga_buffer::cp437_encode('│'), 0xB3);
+}
+
+#[test_case]
+fn cp437_encode_unmapped_falls_back() {
+    assert_eq!(minios::vga_buffer::cp437_encode('\u{1234}'), 0xfe);
+}
+
+// 校验 ColorCode::with_blink 的纯位运算分支：blink=true 必须置上第 7 位，
+// blink=false 必须原样保留 ColorCode::new 算出来的字节（不能顺手清掉
+// 高亮背景色自己的高位，f0e065d 就是因为这个被 04d6269 修掉的）。
+#[test_case]
+fn with_blink_true_sets_bit_seven() {
+    use minios::vga_buffer::{Color, ColorCode};
+    let code = ColorCode::with_blink(Color::Yellow, Color::Blue, true);
+    assert_ne!(code.0 & 0x80, 0);
+}
+
+#[test_case]
+fn with_blink_false_preserves_byte() {
+    use minios::vga_buffer::{Color, ColorCode};
+    let plain = ColorCode::new(Color::Yellow, Color::LightBlue);
+    let unblinked = ColorCode::with_blink(Color::Yellow, Color::LightBlue, false);
+    assert_eq!(unblinked.0, plain.0);
+}
@@ -32,12 +32,24 @@ pub enum Color {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 // 使ColorCode 和 u8 有完全相同的内存布局
 #[repr(transparent)]
-struct ColorCode(u8);
+pub struct ColorCode(pub u8);
 
 impl ColorCode {
-    fn new(foreground: Color, background: Color) -> ColorCode {
+    pub fn new(foreground: Color, background: Color) -> ColorCode {
         ColorCode((background as u8) << 4 | (foreground as u8))
     }
+
+    // 在 VGA 的默认模式下，属性字节的第 7 位（也就是背景色原本的最高位）会被
+    // 复用为闪烁标志，所以开启闪烁之后背景色就只能从 Black..=LightGray（0..=7）
+    // 里选了——这两者是互斥的，调用 Writer::disable_blink 关闭复用后才能拿回
+    // 完整的 16 色背景。
+    pub fn with_blink(foreground: Color, background: Color, blink: bool) -> ColorCode {
+        let ColorCode(byte) = ColorCode::new(foreground, background);
+        // blink == false 必须原样返回，不能顺手清掉第 7 位：在关闭闪烁复用
+        // （调用过 Writer::disable_blink）之后，这一位就是 background 里
+        // 8..=15 那一档高亮色自己的位，不是该函数来决定的。
+        ColorCode(if blink { byte | 0x80 } else { byte })
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -51,6 +63,39 @@ struct ScreenChar {
 const BUFFER_HEIGHT: usize = 25;
 const BUFFER_WIDTH: usize = 80;
 
+// VGA 文本缓冲区里的每个单元格存放的并不是 ASCII，而是 Code Page 437 ——
+// 0x20..=0x7e 与 ASCII 重合，但 0x80..=0xff 是一套独立的图形字符集
+// （带重音的字母、制表符、数学符号等）。CP437_HIGH 按字节 0x80 起的顺序
+// 列出它们各自对应的 Unicode 标量值，cp437_encode 在查不到 ASCII 范围内的
+// 字符时，会在这张表里反查出正确的单元格字节。
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+// 将一个 Unicode 字符翻译为它在 CP437 里对应的单元格字节。
+// ASCII 可打印字符（0x20..=0x7e）直接通过，保持和旧实现一样便宜的快速路径；
+// 查不到的高位字形退回到 0xfe（和改动前对未知字节的处理方式一致）。
+pub fn cp437_encode(c: char) -> u8 {
+    if c.is_ascii() {
+        let byte = c as u8;
+        return match byte {
+            0x20..=0x7e => byte,
+            _ => 0xfe,
+        };
+    }
+    match CP437_HIGH.iter().position(|&glyph| glyph == c) {
+        Some(offset) => 0x80 + offset as u8,
+        None => 0xfe,
+    }
+}
+
 // 对 Buffer 类型，我们再次使用 repr(transparent)，来确保类型和它的单个成员有相同的内存布局。
 #[repr(transparent)]
 struct Buffer {
@@ -96,19 +141,19 @@ impl Writer {
                     color_code: color_code,
                 });
                 self.column_position += 1;
+                self.set_cursor(row, self.column_position);
             }
         }
     }
 
 	pub fn write_string(&mut self, s: &str) {
-        for byte in s.bytes() {
-            match byte {
-                // 可以是能打印的 ASCII 码字节，也可以是换行符
-                0x20..=0x7e | b'\n' => self.write_byte(byte),
-                // 不包含在上述范围之内的字节
-                _ => self.write_byte(0xfe),
+        // 必须按 char 迭代而不是按 byte 迭代：一个多字节的 UTF-8 字符（例如 'é'）
+        // 只应该占用屏幕上的一个格子，而不是被拆成两个 0xfe。
+        for c in s.chars() {
+            match c {
+                '\n' => self.new_line(),
+                c => self.write_byte(cp437_encode(c)),
             }
-
         }
     }
 
@@ -122,8 +167,99 @@ impl Writer {
         }
         self.clear_row(BUFFER_HEIGHT - 1);
         self.column_position = 0;
+        self.set_cursor(BUFFER_HEIGHT - 1, self.column_position);
     }
 
+	// set_blink(true) 给当前的 color_code 打上闪烁位；set_blink(false) 不改动
+	// color_code，之前打上的第 7 位不会被去掉。注意这借用的是背景色的最高位，
+	// 所以开启闪烁之后新写入的字符就只能使用 0..=7 范围内的背景色了，
+	// 参见 ColorCode::with_blink 上的说明。
+	pub fn set_blink(&mut self, blink: bool) {
+		self.color_code = ColorCode(if blink {
+			self.color_code.0 | 0x80
+		} else {
+			// 不能无条件清掉第 7 位：关闭了硬件闪烁复用之后，这一位是
+			// 高亮背景色（8..=15）自己的位，不是闪烁标志。
+			self.color_code.0
+		});
+	}
+
+	// 关闭 VGA 属性控制器里"属性字节第 7 位 = 闪烁"的复用，换回完整的 16 色
+	// 背景；这是写一次寄存器就生效的硬件开关，只需要在需要 16 色背景的场合
+	// 调用一次，和 set_blink 的软件位是两回事。
+	pub fn disable_blink(&self) {
+		use x86_64::instructions::port::Port;
+		unsafe {
+			let mut status_port: Port<u8> = Port::new(0x3DA);
+			let mut index_port: Port<u8> = Port::new(0x3C0);
+			let mut data_port: Port<u8> = Port::new(0x3C1);
+
+			// 索引字节的第 5 位是 Palette Address Source；写 0 会让属性
+			// 控制器把显示切到"加载调色板"模式，屏幕直接黑屏，所以这里
+			// 必须一直带着 0x20，除非是故意要黑屏。
+			let _ = status_port.read();
+			index_port.write(0x10u8 | 0x20);
+			let mode = data_port.read();
+
+			let _ = status_port.read();
+			index_port.write(0x10u8 | 0x20);
+			index_port.write(mode & !0x08);
+		}
+	}
+
+	// 把硬件的文字光标移动到 (row, col)：CRTC 用一个线性偏移量
+	// row * BUFFER_WIDTH + col 来表示光标位置，低字节写进索引 0x0F，
+	// 高字节写进索引 0x0E，索引本身先发给端口 0x3D4，数据发给 0x3D5。
+	pub fn set_cursor(&self, row: usize, col: usize) {
+		use x86_64::instructions::port::Port;
+		let pos = (row * BUFFER_WIDTH + col) as u16;
+		unsafe {
+			let mut index_port: Port<u8> = Port::new(0x3D4);
+			let mut data_port: Port<u8> = Port::new(0x3D5);
+
+			index_port.write(0x0Fu8);
+			data_port.write((pos & 0xff) as u8);
+
+			index_port.write(0x0Eu8);
+			data_port.write((pos >> 8) as u8);
+		}
+	}
+
+	// 打开硬件光标并设置它的扫描线范围（索引 0x0A/0x0B），范围是
+	// 0..=0x1f；start_scanline/end_scanline 之外的位保持寄存器原值不变。
+	pub fn enable_cursor(&self, start_scanline: u8, end_scanline: u8) {
+		use x86_64::instructions::port::Port;
+		unsafe {
+			let mut index_port: Port<u8> = Port::new(0x3D4);
+			let mut data_port: Port<u8> = Port::new(0x3D5);
+
+			index_port.write(0x0Au8);
+			let start = data_port.read();
+			index_port.write(0x0Au8);
+			data_port.write((start & 0xc0) | (start_scanline & 0x1f));
+
+			index_port.write(0x0Bu8);
+			let end = data_port.read();
+			index_port.write(0x0Bu8);
+			data_port.write((end & 0xe0) | (end_scanline & 0x1f));
+		}
+	}
+
+	// 置位光标起始扫描线寄存器(索引 0x0A)的第 5 位即可隐藏硬件光标；
+	// 和 enable_cursor 一样要先读出原值，只改第 5 位，不然会把扫描线范围
+	// （第 0..=4 位）和保留位一起清零。
+	pub fn disable_cursor(&self) {
+		use x86_64::instructions::port::Port;
+		unsafe {
+			let mut index_port: Port<u8> = Port::new(0x3D4);
+			let mut data_port: Port<u8> = Port::new(0x3D5);
+			index_port.write(0x0Au8);
+			let current = data_port.read();
+			index_port.write(0x0Au8);
+			data_port.write((current & 0xdf) | 0x20);
+		}
+	}
+
 	fn clear_row(&mut self, row: usize) {
         let blank = ScreenChar {
             ascii_character: b' ',
@@ -133,6 +269,18 @@ impl Writer {
             self.buffer.chars[row][col].write(blank);
         }
     }
+
+	// 把后续写入使用的前景/背景色换成 color_code，返回换之前的颜色，方便调用者
+	// 在用完之后原样恢复——color_println! 就是靠保存/还原这一对方法实现的。
+	pub fn set_color(&mut self, color_code: ColorCode) -> ColorCode {
+		let previous = self.color_code;
+		self.color_code = color_code;
+		previous
+	}
+
+	pub fn get_color(&self) -> ColorCode {
+		self.color_code
+	}
 }
 // 实现 core::fmt::Write trait；
 impl fmt::Write for Writer {
@@ -179,3 +327,34 @@ pub fn _print(args: fmt::Arguments) { //format_args! 宏将传入的参数搭建
     use core::fmt::Write;
     WRITER.lock().write_fmt(args).unwrap();
 }
+
+// color_print!/color_println! 让调用者临时换一种颜色打印，用完自动恢复成之前
+// 的颜色，不会影响后面其它地方的 println!。
+#[macro_export]
+macro_rules! color_print {
+    ($fg:expr, $bg:expr, $($arg:tt)*) => (
+        $crate::vga_buffer::_color_print($fg, $bg, format_args!($($arg)*))
+    );
+}
+
+#[macro_export]
+macro_rules! color_println {
+    ($fg:expr, $bg:expr) => ($crate::color_print!($fg, $bg, "\n"));
+    ($fg:expr, $bg:expr, $($arg:tt)*) => (
+        $crate::color_print!($fg, $bg, "{}\n", format_args!($($arg)*))
+    );
+}
+
+/*
+_color_print 在同一次 WRITER.lock() 临界区内完成"换色 - 打印 - 恢复"三步，
+这样中断处理程序或者其它 CPU 核心看到的颜色状态永远是完整的一对，不会出现
+换了色但还没恢复、或者恢复了一半的中间状态。
+*/
+#[doc(hidden)]
+pub fn _color_print(foreground: Color, background: Color, args: fmt::Arguments) {
+    use core::fmt::Write;
+    let mut writer = WRITER.lock();
+    let previous = writer.set_color(ColorCode::new(foreground, background));
+    writer.write_fmt(args).unwrap();
+    writer.set_color(previous);
+}